@@ -641,6 +641,9 @@ struct _xmlParserCtxt {
 
     xmlCharEncConvImpl convImpl XML_DEPRECATED_MEMBER;
     void *convCtxt XML_DEPRECATED_MEMBER;
+
+    /* restricted to options for untrusted input */
+    int secure XML_DEPRECATED_MEMBER;
 };
 
 /**
@@ -1897,6 +1900,8 @@ XMLPUBFUN int
 					 const char *encoding);
 XMLPUBFUN int
 		xmlCtxtGetOptions	(xmlParserCtxt *ctxt);
+XMLPUBFUN int
+		xmlCtxtSetSecureDefaults(xmlParserCtxt *ctxt);
 XMLPUBFUN int
 		xmlCtxtSetOptions	(xmlParserCtxt *ctxt,
 					 int options);
@@ -67,7 +67,9 @@ typedef enum {
     /* DTD validation */
     XML_PARSER_VALIDATE = 3,
     /* substitute entities */
-    XML_PARSER_SUBST_ENTITIES = 4
+    XML_PARSER_SUBST_ENTITIES = 4,
+    /* process XInclude (since 2.16.0) */
+    XML_PARSER_XINCLUDE = 5
 } xmlParserProperties;
 
 /**
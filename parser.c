@@ -13066,7 +13066,6 @@ xmlCtxtReset(xmlParserCtxt *ctxt)
     ctxt->inSubset = 0;
     ctxt->errNo = XML_ERR_OK;
     ctxt->depth = 0;
-    ctxt->catalogs = NULL;
     ctxt->sizeentities = 0;
     ctxt->sizeentcopy = 0;
     xmlInitNodeInfoSeq(&ctxt->node_seq);
@@ -13084,6 +13083,7 @@ xmlCtxtReset(xmlParserCtxt *ctxt)
     if (ctxt->catalogs != NULL)
 	xmlCatalogFreeLocal(ctxt->catalogs);
 #endif
+    ctxt->catalogs = NULL;
     ctxt->nbErrors = 0;
     ctxt->nbWarnings = 0;
     if (ctxt->lastError.code != XML_ERR_OK)
@@ -13168,6 +13168,24 @@ xmlCtxtSetOptionsInternal(xmlParserCtxtPtr ctxt, int options, int keepMask)
               XML_PARSE_NO_SYS_CATALOG |
               XML_PARSE_CATALOG_PI;
 
+    /*
+     * Options unsafe for untrusted input can't be enabled after
+     * xmlCtxtSetSecureDefaults.
+     */
+    if (ctxt->secure) {
+        options &= ~(XML_PARSE_NOENT |
+                     XML_PARSE_DTDLOAD |
+                     XML_PARSE_DTDATTR |
+                     XML_PARSE_DTDVALID |
+                     XML_PARSE_HUGE |
+                     XML_PARSE_UNZIP |
+                     XML_PARSE_CATALOG_PI);
+        options |= XML_PARSE_NONET |
+                   XML_PARSE_NO_XXE |
+                   XML_PARSE_NO_SYS_CATALOG;
+        keepMask &= ~XML_PARSE_HUGE;
+    }
+
     ctxt->options = (ctxt->options & keepMask) | (options & allMask);
 
     /*
@@ -13238,6 +13256,46 @@ xmlCtxtGetOptions(xmlParserCtxt *ctxt)
     return(ctxt->options);
 }
 
+/**
+ * Switch the parser context to options suitable for untrusted
+ * input.
+ *
+ * Options that substitute entities, load external DTDs or
+ * entities, validate, process catalog PIs, decompress input or
+ * relax the parser limits are cleared.
+ * XML_PARSE_NONET, XML_PARSE_NO_XXE and XML_PARSE_NO_SYS_CATALOG
+ * are set. Other options are kept. The maximum amplification
+ * factor is reset to its default.
+ *
+ * The restriction persists for the lifetime of the context. Options
+ * passed to #xmlCtxtSetOptions, #xmlCtxtUseOptions or the
+ * xmlCtxtRead* functions later can't enable the cleared options
+ * again.
+ *
+ * XInclude processing isn't handled by the parser. It is
+ * controlled by the caller, for example with #xmlXIncludeProcess
+ * or the xmlTextReader options.
+ *
+ * @since 2.16.0
+ *
+ * @param ctxt  an XML parser context
+ * @returns 0 in case of success, -1 if ctxt is NULL or an HTML
+ *         parser context.
+ */
+int
+xmlCtxtSetSecureDefaults(xmlParserCtxt *ctxt)
+{
+    if ((ctxt == NULL) || (ctxt->html))
+        return(-1);
+
+    ctxt->secure = 1;
+    if (xmlCtxtSetOptionsInternal(ctxt, ctxt->options, 0) != 0)
+        return(-1);
+    ctxt->maxAmpl = XML_MAX_AMPLIFICATION_DEFAULT;
+
+    return(0);
+}
+
 /**
  * Applies the options to the parser context. The following options
  * are never cleared and can only be enabled:
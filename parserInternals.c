@@ -53,12 +53,6 @@
 
 #define XML_MAX_ERRORS 100
 
-/*
- * XML_MAX_AMPLIFICATION_DEFAULT is the default maximum allowed amplification
- * factor of serialized output after entity expansion.
- */
-#define XML_MAX_AMPLIFICATION_DEFAULT 5
-
 /*
  * Various global defaults for parsing
  */
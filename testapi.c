@@ -181,6 +181,7 @@ main(int argc ATTRIBUTE_UNUSED, char **argv ATTRIBUTE_UNUSED) {
     xmlCtxtSetPrivate(NULL, NULL);
     xmlCtxtSetResourceLoader(NULL, 0, NULL);
     xmlCtxtSetSaxHandler(NULL, NULL);
+    xmlCtxtSetSecureDefaults(NULL);
     xmlCtxtUseOptions(NULL, 0);
     xmlCurrentChar(NULL, NULL);
     xmlDOMWrapAdoptNode(NULL, NULL, NULL, NULL, NULL, 0);
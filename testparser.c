@@ -9,16 +9,21 @@
 #include "libxml.h"
 #include <libxml/parser.h>
 #include <libxml/parserInternals.h>
+#include <libxml/relaxng.h>
 #include <libxml/uri.h>
 #include <libxml/xmlreader.h>
 #include <libxml/xmlsave.h>
+#include <libxml/xmlschemas.h>
 #include <libxml/xmlwriter.h>
 #include <libxml/HTMLparser.h>
 #include <libxml/HTMLtree.h>
 
+#include <stdlib.h>
 #include <string.h>
 
-#ifdef LIBXML_SAX1_ENABLED
+#if defined(LIBXML_SAX1_ENABLED) || \
+    (defined(LIBXML_READER_ENABLED) && \
+     (defined(LIBXML_RELAXNG_ENABLED) || defined(LIBXML_SCHEMAS_ENABLED)))
 static void
 ignoreError(void *ctxt ATTRIBUTE_UNUSED,
             const xmlError *error ATTRIBUTE_UNUSED) {
@@ -40,6 +45,49 @@ testNewDocNode(void) {
     return err;
 }
 
+static int
+testDocSetRootElement(void) {
+    xmlDocPtr doc;
+    xmlNodePtr root, old, cur;
+    int err = 0;
+
+    /* A new root must be appended after the prolog. */
+    doc = xmlReadDoc(BAD_CAST "<!DOCTYPE d><!--c--><?pi?><d/>", NULL, NULL,
+                     0);
+    old = xmlDocGetRootElement(doc);
+    xmlUnlinkNode(old);
+    xmlFreeNode(old);
+
+    root = xmlNewDocNode(doc, NULL, BAD_CAST "r", NULL);
+    if (xmlDocSetRootElement(doc, root) != NULL ||
+        doc->last != root ||
+        root->prev == NULL ||
+        root->prev->type != XML_PI_NODE) {
+        fprintf(stderr, "xmlDocSetRootElement failed to append root\n");
+        err = 1;
+    }
+
+    /* Replacing the root must keep it in place. */
+    xmlAddNextSibling(root, xmlNewDocComment(doc, BAD_CAST "e"));
+    cur = xmlNewDocNode(doc, NULL, BAD_CAST "s", NULL);
+    old = xmlDocSetRootElement(doc, cur);
+    if (old != root ||
+        old->parent != NULL ||
+        cur->prev == NULL ||
+        cur->prev->type != XML_PI_NODE ||
+        cur->next == NULL ||
+        cur->next->type != XML_COMMENT_NODE ||
+        doc->children->type != XML_DTD_NODE ||
+        xmlDocGetRootElement(doc) != cur) {
+        fprintf(stderr, "xmlDocSetRootElement failed to replace root\n");
+        err = 1;
+    }
+    xmlFreeNode(old);
+
+    xmlFreeDoc(doc);
+    return err;
+}
+
 static int
 testStandaloneWithEncoding(void) {
     xmlDocPtr doc;
@@ -60,6 +108,68 @@ testStandaloneWithEncoding(void) {
     return err;
 }
 
+static int
+testReadMemoryEmpty(void) {
+    /*
+     * A size of zero means empty input. The buffer must not be
+     * treated as a NUL-terminated string.
+     */
+    const char *bufs[] = { "", "<doc/>" };
+    xmlDocPtr doc;
+    const xmlError *error;
+    size_t i;
+    int err = 0;
+
+    for (i = 0; i < sizeof(bufs) / sizeof(bufs[0]); i++) {
+        xmlResetLastError();
+
+        doc = xmlReadMemory(bufs[i], 0, NULL, NULL, XML_PARSE_NOERROR);
+        error = xmlGetLastError();
+        if (doc != NULL ||
+            error == NULL ||
+            error->code != XML_ERR_DOCUMENT_EMPTY) {
+            fprintf(stderr, "xmlReadMemory with size 0 didn't fail\n");
+            err = 1;
+        }
+        xmlFreeDoc(doc);
+    }
+
+    return err;
+}
+
+static int
+testMultipleRootsRecover(void) {
+    /*
+     * In recovery mode, content after the root element is dropped
+     * but the error is still reported.
+     */
+    xmlParserCtxtPtr ctxt;
+    xmlDocPtr doc;
+    xmlNodePtr root;
+    const xmlError *error;
+    int err = 0;
+
+    ctxt = xmlNewParserCtxt();
+    doc = xmlCtxtReadDoc(ctxt, BAD_CAST "<a/><b/>", NULL, NULL,
+                         XML_PARSE_RECOVER | XML_PARSE_NOERROR);
+    root = xmlDocGetRootElement(doc);
+    if (root == NULL ||
+        !xmlStrEqual(root->name, BAD_CAST "a") ||
+        root->next != NULL) {
+        fprintf(stderr, "multiple roots: unexpected recovery result\n");
+        err = 1;
+    }
+    error = xmlCtxtGetLastError(ctxt);
+    if (error == NULL || error->code != XML_ERR_DOCUMENT_END) {
+        fprintf(stderr, "multiple roots: error not reported\n");
+        err = 1;
+    }
+    xmlFreeDoc(doc);
+    xmlFreeParserCtxt(ctxt);
+
+    return err;
+}
+
 static int
 testUnsupportedEncoding(void) {
     xmlDocPtr doc;
@@ -202,6 +312,146 @@ testInvalidCharRecovery(void) {
     return err;
 }
 
+static int
+testCtxtSetSecureDefaults(void) {
+    const char *xml =
+        "<!DOCTYPE d [<!ENTITY e 'x'>]>\n"
+        "<d>&e;</d>\n";
+    xmlParserCtxtPtr ctxt;
+    xmlParserInputPtr input;
+    xmlDocPtr doc;
+    xmlNodePtr root;
+    int expected;
+    int err = 0;
+
+    ctxt = xmlNewParserCtxt();
+    xmlCtxtSetOptions(ctxt, XML_PARSE_NOENT | XML_PARSE_DTDLOAD |
+                            XML_PARSE_HUGE | XML_PARSE_NOBLANKS);
+    xmlCtxtSetMaxAmplification(ctxt, 1000);
+
+    if (xmlCtxtSetSecureDefaults(ctxt) != 0) {
+        fprintf(stderr, "xmlCtxtSetSecureDefaults failed\n");
+        err = 1;
+    }
+
+    expected = XML_PARSE_NOBLANKS | XML_PARSE_NONET | XML_PARSE_NO_XXE |
+               XML_PARSE_NO_SYS_CATALOG;
+    if (xmlCtxtGetOptions(ctxt) != expected) {
+        fprintf(stderr, "xmlCtxtSetSecureDefaults: wrong options %x\n",
+                xmlCtxtGetOptions(ctxt));
+        err = 1;
+    }
+
+    input = xmlNewInputFromString(NULL, xml, 0);
+    doc = xmlCtxtParseDocument(ctxt, input);
+    root = xmlDocGetRootElement(doc);
+    if (root == NULL ||
+        root->children == NULL ||
+        root->children->type != XML_ENTITY_REF_NODE) {
+        fprintf(stderr, "xmlCtxtSetSecureDefaults: entity substituted\n");
+        err = 1;
+    }
+    xmlFreeDoc(doc);
+
+    /* Options passed to xmlCtxtRead* can't undo the restriction */
+    doc = xmlCtxtReadMemory(ctxt, xml, strlen(xml), NULL, NULL,
+                            XML_PARSE_NOENT | XML_PARSE_DTDLOAD |
+                            XML_PARSE_HUGE);
+    expected = XML_PARSE_NONET | XML_PARSE_NO_XXE | XML_PARSE_NO_SYS_CATALOG;
+    if (xmlCtxtGetOptions(ctxt) != expected) {
+        fprintf(stderr, "xmlCtxtReadMemory: wrong options %x\n",
+                xmlCtxtGetOptions(ctxt));
+        err = 1;
+    }
+    root = xmlDocGetRootElement(doc);
+    if (root == NULL ||
+        root->children == NULL ||
+        root->children->type != XML_ENTITY_REF_NODE) {
+        fprintf(stderr, "xmlCtxtReadMemory: entity substituted\n");
+        err = 1;
+    }
+    xmlFreeDoc(doc);
+
+    xmlFreeParserCtxt(ctxt);
+    return err;
+}
+
+#ifdef LIBXML_CATALOG_ENABLED
+static int testAllocBlocks;
+
+static void *
+testCountMalloc(size_t size) {
+    void *ret = malloc(size);
+
+    if (ret != NULL)
+        testAllocBlocks++;
+    return ret;
+}
+
+static void *
+testCountRealloc(void *ptr, size_t size) {
+    void *ret = realloc(ptr, size);
+
+    if ((ptr == NULL) && (ret != NULL))
+        testAllocBlocks++;
+    return ret;
+}
+
+static void
+testCountFree(void *ptr) {
+    if (ptr != NULL)
+        testAllocBlocks--;
+    free(ptr);
+}
+
+static char *
+testCountStrdup(const char *str) {
+    char *ret = strdup(str);
+
+    if (ret != NULL)
+        testAllocBlocks++;
+    return ret;
+}
+
+static int
+testCtxtResetCatalogs(void) {
+    /*
+     * Document catalogs must be freed when resetting a context.
+     */
+    const char *xml =
+        "<?oasis-xml-catalog catalog=\"catalog.xml\"?>\n"
+        "<doc/>\n";
+    xmlFreeFunc oldFree;
+    xmlMallocFunc oldMalloc;
+    xmlReallocFunc oldRealloc;
+    xmlStrdupFunc oldStrdup;
+    xmlParserCtxtPtr ctxt;
+    xmlDocPtr doc;
+    int err = 0;
+
+    xmlMemGet(&oldFree, &oldMalloc, &oldRealloc, &oldStrdup);
+    xmlMemSetup(testCountFree, testCountMalloc, testCountRealloc,
+                testCountStrdup);
+    testAllocBlocks = 0;
+
+    ctxt = xmlNewParserCtxt();
+    doc = xmlCtxtReadDoc(ctxt, BAD_CAST xml, NULL, NULL,
+                         XML_PARSE_CATALOG_PI);
+    xmlFreeDoc(doc);
+    xmlCtxtReset(ctxt);
+    xmlFreeParserCtxt(ctxt);
+
+    xmlMemSetup(oldFree, oldMalloc, oldRealloc, oldStrdup);
+
+    if (testAllocBlocks != 0) {
+        fprintf(stderr, "xmlCtxtReset leaked %d blocks\n", testAllocBlocks);
+        err = 1;
+    }
+
+    return err;
+}
+#endif /* LIBXML_CATALOG_ENABLED */
+
 static void
 testCtxtInputGetterError(void *errCtxt, const xmlError *error) {
     int *err = errCtxt;
@@ -499,6 +749,48 @@ testDocDumpFormatMemoryEnc(void) {
     xmlFreeDoc(doc);
     return err;
 }
+
+static int
+testDocDumpCharRefFallback(void) {
+    /*
+     * Characters that can't be represented in the output encoding
+     * must be written as character references.
+     */
+    static const char *const tests[] = {
+        "iso-8859-1",
+        "<doc a=\"\xE2\x82\xAC\">\xC3\x98\xE2\x82\xAC</doc>",
+        "<?xml version=\"1.0\" encoding=\"iso-8859-1\"?>\n"
+        "<doc a=\"&#8364;\">\xD8&#8364;</doc>\n",
+#if defined(LIBXML_ICONV_ENABLED) || defined(LIBXML_ICU_ENABLED)
+        /* U+0100 isn't part of any Shift_JIS variant */
+        "Shift_JIS",
+        "<doc a=\"\xC4\x80\">\xE6\x97\xA5\xC4\x80</doc>",
+        "<?xml version=\"1.0\" encoding=\"Shift_JIS\"?>\n"
+        "<doc a=\"&#256;\">\x93\xFA&#256;</doc>\n",
+#endif
+    };
+    xmlDocPtr doc;
+    xmlChar *text;
+    int len;
+    int err = 0;
+    size_t i;
+
+    for (i = 0; i < sizeof(tests) / sizeof(tests[0]); i += 3) {
+        doc = xmlReadDoc(BAD_CAST tests[i+1], NULL, NULL, 0);
+        xmlDocDumpFormatMemoryEnc(doc, &text, &len, tests[i], 0);
+
+        if ((text == NULL) || (strcmp((char *) text, tests[i+2]) != 0)) {
+            fprintf(stderr, "testDocDumpCharRefFallback failed for %s\n",
+                    tests[i]);
+            err = 1;
+        }
+
+        xmlFree(text);
+        xmlFreeDoc(doc);
+    }
+
+    return err;
+}
 #endif /* LIBXML_OUTPUT_ENABLED */
 
 #ifdef LIBXML_SAX1_ENABLED
@@ -1003,6 +1295,48 @@ testReader(void) {
     return err;
 }
 
+static int
+testReaderNextSibling(void) {
+    xmlTextReader *reader;
+    const xmlChar *xml = BAD_CAST
+        "<d><a><x/>text</a>t<b/></d>";
+    int err = 0;
+
+    reader = xmlReaderForDoc(xml, NULL, NULL, 0);
+
+    /* d, a */
+    xmlTextReaderRead(reader);
+    xmlTextReaderRead(reader);
+
+    if (xmlTextReaderNextSibling(reader) != 1 ||
+        xmlTextReaderNodeType(reader) != XML_READER_TYPE_TEXT ||
+        xmlTextReaderDepth(reader) != 1) {
+        fprintf(stderr, "xmlTextReaderNextSibling failed to skip subtree\n");
+        err = 1;
+    }
+
+    if (xmlTextReaderNextSibling(reader) != 1 ||
+        !xmlStrEqual(xmlTextReaderConstName(reader), BAD_CAST "b")) {
+        fprintf(stderr, "xmlTextReaderNextSibling failed to move to b\n");
+        err = 1;
+    }
+
+    if (xmlTextReaderNextSibling(reader) != 0 ||
+        xmlTextReaderNodeType(reader) != XML_READER_TYPE_END_ELEMENT ||
+        !xmlStrEqual(xmlTextReaderConstName(reader), BAD_CAST "d")) {
+        fprintf(stderr, "xmlTextReaderNextSibling failed at last child\n");
+        err = 1;
+    }
+
+    if (xmlTextReaderNextSibling(reader) != 0) {
+        fprintf(stderr, "xmlTextReaderNextSibling failed at end\n");
+        err = 1;
+    }
+
+    xmlFreeTextReader(reader);
+    return err;
+}
+
 #ifdef LIBXML_XINCLUDE_ENABLED
 typedef struct {
     char *message;
@@ -1080,9 +1414,194 @@ testReaderXIncludeError(void) {
 
     return err;
 }
+
+static xmlParserErrors
+testReaderXIncludeLoader(void *ctxt ATTRIBUTE_UNUSED, const char *url,
+                         const char *publicId ATTRIBUTE_UNUSED,
+                         xmlResourceType type ATTRIBUTE_UNUSED,
+                         xmlParserInputFlags flags ATTRIBUTE_UNUSED,
+                         xmlParserInputPtr *out) {
+    if (strcmp(url, "inc.xml") != 0) {
+        *out = NULL;
+        return XML_IO_ENOENT;
+    }
+
+    *out = xmlNewInputFromString(url, "<inc/>", 0);
+    return (*out == NULL) ? XML_ERR_NO_MEMORY : XML_ERR_OK;
+}
+
+static int
+testReaderXIncludeProp(void) {
+    const char *doc =
+        "<doc xmlns:xi='http://www.w3.org/2001/XInclude'>"
+        "<xi:include href='inc.xml'/>"
+        "</doc>";
+    xmlTextReader *reader;
+    int found = 0;
+    int err = 0;
+
+    reader = xmlReaderForDoc(BAD_CAST doc, NULL, NULL, 0);
+    xmlTextReaderSetResourceLoader(reader, testReaderXIncludeLoader, NULL);
+
+    if (xmlTextReaderGetParserProp(reader, XML_PARSER_XINCLUDE) != 0 ||
+        xmlTextReaderSetParserProp(reader, XML_PARSER_XINCLUDE, 1) != 0 ||
+        xmlTextReaderGetParserProp(reader, XML_PARSER_XINCLUDE) != 1) {
+        fprintf(stderr, "XML_PARSER_XINCLUDE property not supported\n");
+        err = 1;
+    }
+
+    while (xmlTextReaderRead(reader) > 0) {
+        const xmlChar *name = xmlTextReaderConstName(reader);
+
+        if (xmlStrEqual(name, BAD_CAST "inc"))
+            found = 1;
+        if (xmlStrEqual(name, BAD_CAST "xi:include")) {
+            fprintf(stderr, "XML_PARSER_XINCLUDE: include not expanded\n");
+            err = 1;
+        }
+    }
+
+    if (!found) {
+        fprintf(stderr, "XML_PARSER_XINCLUDE: included content missing\n");
+        err = 1;
+    }
+
+    xmlFreeTextReader(reader);
+    return err;
+}
 #endif
+
+#if defined(LIBXML_RELAXNG_ENABLED) || defined(LIBXML_SCHEMAS_ENABLED)
+static const char *const testReaderSchemaDocs[] = {
+    "<doc><a/><b/></doc>",
+    "<doc><b/></doc>",
+    "<doc><a/><b/></doc>"
+};
+
+static int
+testReaderSchemaDocValid(xmlTextReader *reader, int i, const char *what) {
+    int expected = (i != 1);
+    int ret;
+
+    while (xmlTextReaderRead(reader) > 0)
+        ;
+
+    ret = xmlTextReaderIsValid(reader);
+    if (ret != expected) {
+        fprintf(stderr, "%s: document %d: expected %d, got %d\n",
+                what, i, expected, ret);
+        return 1;
+    }
+
+    return 0;
+}
 #endif
 
+#ifdef LIBXML_RELAXNG_ENABLED
+static int
+testReaderRelaxNGSchemaReuse(void) {
+    /*
+     * Test whether a precompiled RelaxNG schema can be shared by
+     * several readers.
+     */
+    const char *rng =
+        "<element name='doc' xmlns='http://relaxng.org/ns/structure/1.0'>\n"
+        "  <element name='a'><empty/></element>\n"
+        "  <element name='b'><empty/></element>\n"
+        "</element>\n";
+    xmlRelaxNGParserCtxt *pctxt;
+    xmlRelaxNG *schema;
+    xmlTextReader *reader;
+    size_t i;
+    int err = 0;
+
+    pctxt = xmlRelaxNGNewMemParserCtxt(rng, strlen(rng));
+    schema = xmlRelaxNGParse(pctxt);
+    xmlRelaxNGFreeParserCtxt(pctxt);
+    if (schema == NULL) {
+        fprintf(stderr, "testReaderRelaxNGSchemaReuse: parse failed\n");
+        return 1;
+    }
+
+    for (i = 0; i < sizeof(testReaderSchemaDocs) / sizeof(char *); i++) {
+        reader = xmlReaderForDoc(BAD_CAST testReaderSchemaDocs[i],
+                                 NULL, NULL, 0);
+        xmlTextReaderSetStructuredErrorHandler(reader, ignoreError, NULL);
+
+        if (xmlTextReaderRelaxNGSetSchema(reader, schema) != 0) {
+            fprintf(stderr, "xmlTextReaderRelaxNGSetSchema failed\n");
+            err = 1;
+        } else {
+            err |= testReaderSchemaDocValid(reader, i,
+                                            "testReaderRelaxNGSchemaReuse");
+        }
+
+        xmlFreeTextReader(reader);
+    }
+
+    xmlRelaxNGFree(schema);
+    return err;
+}
+#endif /* LIBXML_RELAXNG_ENABLED */
+
+#ifdef LIBXML_SCHEMAS_ENABLED
+static int
+testReaderSchemaValidCtxtReuse(void) {
+    /*
+     * Test whether a precompiled XML Schema and a single validation
+     * context can be shared by several readers.
+     */
+    const char *xsd =
+        "<xs:schema xmlns:xs='http://www.w3.org/2001/XMLSchema'>\n"
+        "  <xs:element name='doc'>\n"
+        "    <xs:complexType>\n"
+        "      <xs:sequence>\n"
+        "        <xs:element name='a'/>\n"
+        "        <xs:element name='b'/>\n"
+        "      </xs:sequence>\n"
+        "    </xs:complexType>\n"
+        "  </xs:element>\n"
+        "</xs:schema>\n";
+    xmlSchemaParserCtxt *pctxt;
+    xmlSchema *schema;
+    xmlSchemaValidCtxt *vctxt;
+    xmlTextReader *reader;
+    size_t i;
+    int err = 0;
+
+    pctxt = xmlSchemaNewMemParserCtxt(xsd, strlen(xsd));
+    schema = xmlSchemaParse(pctxt);
+    xmlSchemaFreeParserCtxt(pctxt);
+    if (schema == NULL) {
+        fprintf(stderr, "testReaderSchemaValidCtxtReuse: parse failed\n");
+        return 1;
+    }
+
+    vctxt = xmlSchemaNewValidCtxt(schema);
+    xmlSchemaSetValidStructuredErrors(vctxt, ignoreError, NULL);
+
+    for (i = 0; i < sizeof(testReaderSchemaDocs) / sizeof(char *); i++) {
+        reader = xmlReaderForDoc(BAD_CAST testReaderSchemaDocs[i],
+                                 NULL, NULL, 0);
+
+        if (xmlTextReaderSchemaValidateCtxt(reader, vctxt, 0) != 0) {
+            fprintf(stderr, "xmlTextReaderSchemaValidateCtxt failed\n");
+            err = 1;
+        } else {
+            err |= testReaderSchemaDocValid(reader, i,
+                                            "testReaderSchemaValidCtxtReuse");
+        }
+
+        xmlFreeTextReader(reader);
+    }
+
+    xmlSchemaFreeValidCtxt(vctxt);
+    xmlSchemaFree(schema);
+    return err;
+}
+#endif /* LIBXML_SCHEMAS_ENABLED */
+#endif /* LIBXML_READER_ENABLED */
+
 #ifdef LIBXML_WRITER_ENABLED
 static int
 testWriterIOWrite(void *ctxt, const char *data, int len) {
@@ -1124,6 +1643,110 @@ testWriterClose(void){
     xmlFreeTextWriter(writer);
     return err;
 }
+
+static int
+testWriterNsDecls(void) {
+    xmlBufferPtr buf;
+    xmlTextWriterPtr writer;
+    const char *expected =
+        "<p:a xmlns:p=\"urn:x\">"
+        "<p:b p:c=\"v\" q:d=\"v\" xmlns:q=\"urn:y\">"
+        "<e xmlns=\"urn:z\"><f/><p:g xmlns:p=\"urn:w\"/></e>"
+        "</p:b>"
+        "<q:h xmlns:q=\"urn:y\"/>"
+        "</p:a>";
+    int err = 0;
+
+    buf = xmlBufferCreate();
+    writer = xmlNewTextWriterMemory(buf, 0);
+    xmlTextWriterStartElementNS(writer, BAD_CAST "p", BAD_CAST "a",
+                                BAD_CAST "urn:x");
+    xmlTextWriterStartElementNS(writer, BAD_CAST "p", BAD_CAST "b",
+                                BAD_CAST "urn:x");
+    xmlTextWriterWriteAttributeNS(writer, BAD_CAST "p", BAD_CAST "c",
+                                  BAD_CAST "urn:x", BAD_CAST "v");
+    xmlTextWriterWriteAttributeNS(writer, BAD_CAST "q", BAD_CAST "d",
+                                  BAD_CAST "urn:y", BAD_CAST "v");
+    xmlTextWriterStartElementNS(writer, NULL, BAD_CAST "e",
+                                BAD_CAST "urn:z");
+    xmlTextWriterStartElementNS(writer, NULL, BAD_CAST "f",
+                                BAD_CAST "urn:z");
+    xmlTextWriterEndElement(writer);
+    xmlTextWriterStartElementNS(writer, BAD_CAST "p", BAD_CAST "g",
+                                BAD_CAST "urn:w");
+    xmlTextWriterEndElement(writer);
+    xmlTextWriterEndElement(writer);
+    xmlTextWriterEndElement(writer);
+    xmlTextWriterStartElementNS(writer, BAD_CAST "q", BAD_CAST "h",
+                                BAD_CAST "urn:y");
+    xmlTextWriterEndElement(writer);
+    xmlTextWriterEndElement(writer);
+    xmlFreeTextWriter(writer);
+
+    if (strcmp((const char *) xmlBufferContent(buf), expected) != 0) {
+        fprintf(stderr, "xmlTextWriter: unexpected ns decls: %s\n",
+                (const char *) xmlBufferContent(buf));
+        err = 1;
+    }
+
+    xmlBufferFree(buf);
+
+    /* Rebinding the inherited prefix of the element must fail */
+    buf = xmlBufferCreate();
+    writer = xmlNewTextWriterMemory(buf, 0);
+    xmlTextWriterStartElementNS(writer, BAD_CAST "p", BAD_CAST "a",
+                                BAD_CAST "urn:x");
+    xmlTextWriterStartElementNS(writer, BAD_CAST "p", BAD_CAST "b",
+                                BAD_CAST "urn:x");
+    if (xmlTextWriterWriteAttributeNS(writer, BAD_CAST "p", BAD_CAST "c",
+                                      BAD_CAST "urn:y", BAD_CAST "v") >= 0) {
+        fprintf(stderr, "xmlTextWriter: prefix mismatch not detected\n");
+        err = 1;
+    }
+    xmlFreeTextWriter(writer);
+    xmlBufferFree(buf);
+
+    /* A prefix declared as plain attribute can't be declared again */
+    buf = xmlBufferCreate();
+    writer = xmlNewTextWriterMemory(buf, 0);
+    xmlTextWriterStartElement(writer, BAD_CAST "m");
+    xmlTextWriterWriteAttribute(writer, BAD_CAST "xmlns:p", BAD_CAST "urn:x");
+    if (xmlTextWriterWriteAttributeNS(writer, BAD_CAST "p", BAD_CAST "c",
+                                      BAD_CAST "urn:y", BAD_CAST "v") >= 0) {
+        fprintf(stderr, "xmlTextWriter: duplicate ns decl not detected\n");
+        err = 1;
+    }
+    xmlFreeTextWriter(writer);
+    xmlBufferFree(buf);
+
+    /* Declarations written as plain attributes hide ancestor bindings */
+    buf = xmlBufferCreate();
+    writer = xmlNewTextWriterMemory(buf, 0);
+    xmlTextWriterStartElementNS(writer, BAD_CAST "p", BAD_CAST "a",
+                                BAD_CAST "urn:x");
+    xmlTextWriterStartElement(writer, BAD_CAST "m");
+    xmlTextWriterWriteAttribute(writer, BAD_CAST "xmlns:p",
+                                BAD_CAST "urn:other");
+    xmlTextWriterStartElementNS(writer, BAD_CAST "p", BAD_CAST "c",
+                                BAD_CAST "urn:x");
+    xmlTextWriterEndElement(writer);
+    xmlTextWriterEndElement(writer);
+    xmlTextWriterEndElement(writer);
+    xmlFreeTextWriter(writer);
+
+    expected =
+        "<p:a xmlns:p=\"urn:x\">"
+        "<m xmlns:p=\"urn:other\"><p:c xmlns:p=\"urn:x\"/></m>"
+        "</p:a>";
+    if (strcmp((const char *) xmlBufferContent(buf), expected) != 0) {
+        fprintf(stderr, "xmlTextWriter: unexpected ns decls: %s\n",
+                (const char *) xmlBufferContent(buf));
+        err = 1;
+    }
+
+    xmlBufferFree(buf);
+    return err;
+}
 #endif
 
 typedef struct {
@@ -1492,13 +2115,20 @@ main(void) {
     int err = 0;
 
     err |= testNewDocNode();
+    err |= testDocSetRootElement();
     err |= testStandaloneWithEncoding();
+    err |= testReadMemoryEmpty();
+    err |= testMultipleRootsRecover();
     err |= testUnsupportedEncoding();
     err |= testNodeGetContent();
     err |= testCFileIO();
     err |= testUndeclEntInContent();
     err |= testInvalidCharRecovery();
     err |= testCtxtInputGetters();
+    err |= testCtxtSetSecureDefaults();
+#ifdef LIBXML_CATALOG_ENABLED
+    err |= testCtxtResetCatalogs();
+#endif
 #ifdef LIBXML_VALID_ENABLED
     err |= testSwitchDtd();
 #endif
@@ -1507,6 +2137,7 @@ main(void) {
     err |= testNoBlanks();
     err |= testSaveNullEnc();
     err |= testDocDumpFormatMemoryEnc();
+    err |= testDocDumpCharRefFallback();
 #endif
 #ifdef LIBXML_SAX1_ENABLED
     err |= testBalancedChunk();
@@ -1532,12 +2163,21 @@ main(void) {
     err |= testReaderContent();
 #endif
     err |= testReader();
+    err |= testReaderNextSibling();
 #ifdef LIBXML_XINCLUDE_ENABLED
     err |= testReaderXIncludeError();
+    err |= testReaderXIncludeProp();
+#endif
+#ifdef LIBXML_RELAXNG_ENABLED
+    err |= testReaderRelaxNGSchemaReuse();
+#endif
+#ifdef LIBXML_SCHEMAS_ENABLED
+    err |= testReaderSchemaValidCtxtReuse();
 #endif
 #endif
 #ifdef LIBXML_WRITER_ENABLED
     err |= testWriterClose();
+    err |= testWriterNsDecls();
 #endif
     err |= testBuildRelativeUri();
 #if defined(_WIN32) || defined(__CYGWIN__)
@@ -1939,21 +1939,35 @@ found_node:
 }
 
 /**
- * Skip to the node following the current one in document order while
- * avoiding the subtree if any.
- * Currently implemented only for Readers built on a document
+ * Skip to the next sibling of the current node while avoiding the
+ * subtree if any.
+ *
+ * For readers built on a document, the reader doesn't move if
+ * there's no next sibling. When streaming, the end of the parent
+ * element has to be read to find out, so the reader is left on
+ * the parent's end tag in this case.
  *
  * @param reader  the xmlTextReader used
- * @returns 1 if the node was read successfully, 0 if there is no more
- *          nodes to read, or -1 in case of error
+ * @returns 1 if the node was read successfully, 0 if there is no
+ *          next sibling, or -1 in case of error
  */
 int
 xmlTextReaderNextSibling(xmlTextReader *reader) {
     if (reader == NULL)
         return(-1);
     if (reader->doc == NULL) {
-        /* TODO */
-	return(-1);
+        int depth, ret;
+
+        if (reader->node == NULL)
+            return(xmlTextReaderRead(reader));
+
+        depth = reader->depth;
+        ret = xmlTextReaderNext(reader);
+        if (ret != 1)
+            return(ret);
+        if (reader->depth != depth)
+            return(0);
+        return(1);
     }
 
     if (reader->state == XML_TEXTREADER_END)
@@ -3715,6 +3729,19 @@ xmlTextReaderSetParserProp(xmlTextReader *reader, int prop, int value) {
 		ctxt->replaceEntities = 0;
 	    }
 	    return(0);
+        case XML_PARSER_XINCLUDE:
+#ifdef LIBXML_XINCLUDE_ENABLED
+            if (value != 0) {
+                reader->parserFlags |= XML_PARSE_XINCLUDE;
+                reader->xinclude = 1;
+            } else {
+                reader->parserFlags &= ~XML_PARSE_XINCLUDE;
+                reader->xinclude = 0;
+            }
+            return(0);
+#else
+            return((value != 0) ? -1 : 0);
+#endif
     }
     return(-1);
 }
@@ -3748,6 +3775,12 @@ xmlTextReaderGetParserProp(xmlTextReader *reader, int prop) {
 	    return(reader->validate);
 	case XML_PARSER_SUBST_ENTITIES:
 	    return(ctxt->replaceEntities);
+        case XML_PARSER_XINCLUDE:
+#ifdef LIBXML_XINCLUDE_ENABLED
+            return(reader->xinclude);
+#else
+            return(0);
+#endif
     }
     return(-1);
 }
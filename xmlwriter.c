@@ -81,6 +81,7 @@ struct _xmlTextWriter {
     xmlOutputBufferPtr out;     /* output buffer */
     xmlListPtr nodes;           /* element name stack */
     xmlListPtr nsstack;         /* name spaces stack */
+    xmlListPtr nsscope;         /* name space declarations in scope */
     int level;
     int indent;                 /* enable indent */
     int doindent;               /* internal indent flag */
@@ -98,6 +99,15 @@ static int xmlTextWriterOutputNSDecl(xmlTextWriterPtr writer);
 static void xmlFreeTextWriterNsStackEntry(xmlLinkPtr lk);
 static int xmlCmpTextWriterNsStackEntry(const void *data0,
                                         const void *data1);
+static int xmlTextWriterNsInScope(xmlTextWriterPtr writer,
+                                  const xmlChar *prefix,
+                                  const xmlChar *namespaceURI);
+static int xmlTextWriterPushNsScope(xmlTextWriterPtr writer,
+                                    const xmlChar *prefix,
+                                    const xmlChar *namespaceURI,
+                                    xmlLinkPtr elem);
+static void xmlTextWriterPopNsScope(xmlTextWriterPtr writer,
+                                    xmlLinkPtr elem);
 static int xmlTextWriterWriteDocCallback(void *context,
                                          const char *str, int len);
 static int xmlTextWriterCloseDocCallback(void *context);
@@ -194,6 +204,17 @@ xmlNewTextWriter(xmlOutputBuffer *out)
         return NULL;
     }
 
+    ret->nsscope = xmlListCreate(xmlFreeTextWriterNsStackEntry,
+                                 xmlCmpTextWriterNsStackEntry);
+    if (ret->nsscope == NULL) {
+        xmlWriterErrMsg(NULL, XML_ERR_NO_MEMORY,
+                        "xmlNewTextWriter : out of memory!\n");
+        xmlListDelete(ret->nodes);
+        xmlListDelete(ret->nsstack);
+        xmlFree(ret);
+        return NULL;
+    }
+
     ret->out = out;
     ret->ichar = xmlStrdup(BAD_CAST " ");
     ret->qchar = '"';
@@ -201,6 +222,7 @@ xmlNewTextWriter(xmlOutputBuffer *out)
     if (!ret->ichar) {
         xmlListDelete(ret->nodes);
         xmlListDelete(ret->nsstack);
+        xmlListDelete(ret->nsscope);
         xmlFree(ret);
         xmlWriterErrMsg(NULL, XML_ERR_NO_MEMORY,
                         "xmlNewTextWriter : out of memory!\n");
@@ -458,6 +480,9 @@ xmlFreeTextWriter(xmlTextWriter *writer)
     if (writer->nsstack != NULL)
         xmlListDelete(writer->nsstack);
 
+    if (writer->nsscope != NULL)
+        xmlListDelete(writer->nsscope);
+
     if (writer->ctxt != NULL) {
         if ((writer->ctxt->myDoc != NULL) && (writer->no_doc_free == 0)) {
 	    xmlFreeDoc(writer->ctxt->myDoc);
@@ -999,6 +1024,10 @@ xmlTextWriterStartElement(xmlTextWriter *writer, const xmlChar * name)
 /**
  * Start an xml element with namespace support.
  *
+ * If namespaceURI is not NULL, a namespace declaration is written
+ * for the prefix unless an ancestor element already binds the
+ * prefix to the same namespace URI.
+ *
  * @param writer  the xmlTextWriter
  * @param prefix  namespace prefix or NULL
  * @param name  element local name
@@ -1032,13 +1061,7 @@ xmlTextWriterStartElementNS(xmlTextWriter *writer,
     sum += count;
 
     if (namespaceURI != 0) {
-        xmlTextWriterNsStackEntry *p = (xmlTextWriterNsStackEntry *)
-        xmlMalloc(sizeof(xmlTextWriterNsStackEntry));
-        if (p == 0) {
-            xmlWriterErrMsg(writer, XML_ERR_NO_MEMORY,
-                            "xmlTextWriterStartElementNS : out of memory!\n");
-            return -1;
-        }
+        xmlTextWriterNsStackEntry *p;
 
         buf = xmlStrdup(BAD_CAST "xmlns");
         if (prefix != 0) {
@@ -1046,6 +1069,28 @@ xmlTextWriterStartElementNS(xmlTextWriter *writer,
             buf = xmlStrcat(buf, prefix);
         }
 
+        /*
+         * Don't redeclare a namespace inherited from an ancestor but
+         * record the binding for the element.
+         */
+        if (xmlTextWriterNsInScope(writer, buf, namespaceURI)) {
+            count = xmlTextWriterPushNsScope(writer, buf, namespaceURI,
+                                             xmlListFront(writer->nodes));
+            xmlFree(buf);
+            if (count < 0)
+                return -1;
+            return sum;
+        }
+
+        p = (xmlTextWriterNsStackEntry *)
+            xmlMalloc(sizeof(xmlTextWriterNsStackEntry));
+        if (p == 0) {
+            xmlWriterErrMsg(writer, XML_ERR_NO_MEMORY,
+                            "xmlTextWriterStartElementNS : out of memory!\n");
+            xmlFree(buf);
+            return -1;
+        }
+
         p->prefix = buf;
         p->uri = xmlStrdup(namespaceURI);
         if (p->uri == 0) {
@@ -1148,6 +1193,7 @@ xmlTextWriterEndElement(xmlTextWriter *writer)
         sum += count;
     }
 
+    xmlTextWriterPopNsScope(writer, lk);
     xmlListPopFront(writer->nodes);
     return sum;
 }
@@ -1229,6 +1275,7 @@ xmlTextWriterFullEndElement(xmlTextWriter *writer)
         sum += count;
     }
 
+    xmlTextWriterPopNsScope(writer, lk);
     xmlListPopFront(writer->nodes);
     return sum;
 }
@@ -1736,12 +1783,27 @@ xmlTextWriterStartAttribute(xmlTextWriter *writer, const xmlChar * name)
             return -1;
     }
 
+    /*
+     * Namespace declarations written as plain attributes hide the
+     * bindings of the ancestors. The URI isn't known here.
+     */
+    if ((xmlStrEqual(name, BAD_CAST "xmlns")) ||
+        (xmlStrncmp(name, BAD_CAST "xmlns:", 6) == 0)) {
+        if (xmlTextWriterPushNsScope(writer, name, NULL, lk) < 0)
+            return -1;
+    }
+
     return sum;
 }
 
 /**
  * Start an xml attribute with namespace support.
  *
+ * If namespaceURI is not NULL, a namespace declaration is written
+ * for the prefix unless the current element or one of its ancestors
+ * already binds the prefix to the same namespace URI. Binding a
+ * prefix of the current element to another URI is an error.
+ *
  * @param writer  the xmlTextWriter
  * @param prefix  namespace prefix or NULL
  * @param name  element local name
@@ -1777,15 +1839,27 @@ xmlTextWriterStartAttributeNS(xmlTextWriter *writer,
 
         curns = (xmlTextWriterNsStackEntry *)xmlListSearch(writer->nsstack,
                                                            (void *)&nsentry);
-        if ((curns != NULL)) {
+        if (curns == NULL)
+            curns = (xmlTextWriterNsStackEntry *)
+                xmlListSearch(writer->nsscope, (void *)&nsentry);
+        if (curns != NULL) {
             xmlFree(buf);
-            if (xmlStrcmp(curns->uri, namespaceURI) == 0) {
+            if ((curns->uri != NULL) &&
+                (xmlStrcmp(curns->uri, namespaceURI) == 0)) {
                 /* Namespace already defined on element skip */
                 buf = NULL;
             } else {
-                /* Prefix mismatch so error out */
+                /* Prefix mismatch or unknown binding so error out */
                 return -1;
             }
+        } else if (xmlTextWriterNsInScope(writer, buf, namespaceURI)) {
+            /* Namespace inherited from an ancestor, skip */
+            count = xmlTextWriterPushNsScope(writer, buf, namespaceURI,
+                                             nsentry.elem);
+            xmlFree(buf);
+            if (count < 0)
+                return -1;
+            buf = NULL;
         }
 
         /* Do not add namespace decl to list - it is already there */
@@ -4183,20 +4257,153 @@ xmlTextWriterOutputNSDecl(xmlTextWriterPtr writer)
 
         if (np != 0) {
             count = xmlTextWriterWriteAttribute(writer, prefix, namespaceURI);
-            xmlFree(namespaceURI);
-            xmlFree(prefix);
 
             if (count < 0) {
+                xmlFree(namespaceURI);
+                xmlFree(prefix);
                 xmlListDelete(writer->nsstack);
                 writer->nsstack = NULL;
                 return -1;
             }
             sum += count;
+
+            /*
+             * The attribute was recorded with an unknown URI by
+             * xmlTextWriterStartAttribute.
+             */
+            np = NULL;
+            if (!xmlListEmpty(writer->nsscope)) {
+                lk = xmlListFront(writer->nsscope);
+                np = (xmlTextWriterNsStackEntry *) xmlLinkGetData(lk);
+            }
+            if ((np != NULL) && (np->uri == NULL) &&
+                (xmlStrEqual(np->prefix, prefix))) {
+                np->uri = namespaceURI;
+                namespaceURI = NULL;
+            }
+            xmlFree(namespaceURI);
+            xmlFree(prefix);
         }
     }
     return sum;
 }
 
+typedef struct {
+    const xmlChar *prefix;
+    const xmlChar *uri;
+    int found;
+} xmlTextWriterNsLookup;
+
+static int
+xmlTextWriterNsLookupWalker(const void *data, void *user)
+{
+    const xmlTextWriterNsStackEntry *np = data;
+    xmlTextWriterNsLookup *lookup = user;
+
+    if (xmlStrEqual(np->prefix, lookup->prefix)) {
+        lookup->found = xmlStrEqual(np->uri, lookup->uri);
+        return 0;
+    }
+
+    return 1;
+}
+
+/**
+ * Check whether the innermost declaration of a prefix already in
+ * scope binds it to the namespace URI.
+ *
+ * @param writer  the xmlTextWriter
+ * @param prefix  the declaration name (xmlns or xmlns:prefix)
+ * @param namespaceURI  the namespace URI
+ * @returns 1 if the namespace is in scope, 0 otherwise
+ */
+static int
+xmlTextWriterNsInScope(xmlTextWriterPtr writer, const xmlChar *prefix,
+                       const xmlChar *namespaceURI)
+{
+    xmlTextWriterNsLookup lookup;
+
+    if ((writer->nsscope == NULL) || (prefix == NULL))
+        return 0;
+
+    lookup.prefix = prefix;
+    lookup.uri = namespaceURI;
+    lookup.found = 0;
+    xmlListWalk(writer->nsscope, xmlTextWriterNsLookupWalker, &lookup);
+
+    return lookup.found;
+}
+
+/**
+ * Record a namespace declaration for the scope of an element.
+ *
+ * @param writer  the xmlTextWriter
+ * @param prefix  the declaration name (xmlns or xmlns:prefix)
+ * @param namespaceURI  the namespace URI or NULL if unknown
+ * @param elem  the link of the element in the element stack
+ * @returns 0 on success, -1 on error
+ */
+static int
+xmlTextWriterPushNsScope(xmlTextWriterPtr writer, const xmlChar *prefix,
+                         const xmlChar *namespaceURI, xmlLinkPtr elem)
+{
+    xmlTextWriterNsStackEntry *np;
+
+    np = (xmlTextWriterNsStackEntry *)
+        xmlMalloc(sizeof(xmlTextWriterNsStackEntry));
+    if (np == NULL)
+        goto error;
+    np->prefix = xmlStrdup(prefix);
+    np->uri = NULL;
+    np->elem = elem;
+    if (np->prefix == NULL)
+        goto error;
+    if (namespaceURI != NULL) {
+        np->uri = xmlStrdup(namespaceURI);
+        if (np->uri == NULL)
+            goto error;
+    }
+    if (xmlListPushFront(writer->nsscope, np) == 0)
+        goto error;
+
+    return 0;
+
+error:
+    xmlWriterErrMsg(writer, XML_ERR_NO_MEMORY,
+                    "xmlTextWriterPushNsScope : out of memory!\n");
+    if (np != NULL) {
+        xmlFree(np->prefix);
+        xmlFree(np->uri);
+        xmlFree(np);
+    }
+    return -1;
+}
+
+/**
+ * Remove the namespace declarations of an element that is being
+ * closed from the scope.
+ *
+ * @param writer  the xmlTextWriter
+ * @param elem  the link of the element in the element stack
+ */
+static void
+xmlTextWriterPopNsScope(xmlTextWriterPtr writer, xmlLinkPtr elem)
+{
+    xmlLinkPtr lk;
+    xmlTextWriterNsStackEntry *np;
+
+    if (writer->nsscope == NULL)
+        return;
+
+    while (!xmlListEmpty(writer->nsscope)) {
+        lk = xmlListFront(writer->nsscope);
+        np = (xmlTextWriterNsStackEntry *) xmlLinkGetData(lk);
+        if ((np != NULL) && (np->elem != elem))
+            break;
+        xmlListPopFront(writer->nsscope);
+    }
+}
+
 /**
  * Free callback for the xmlList.
  *